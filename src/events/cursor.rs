@@ -0,0 +1,135 @@
+//! Tracks the cursor presentation conrod would like the window backend to show, separately
+//! from the raw input conrod receives about the mouse.
+
+/// The icon the window backend should use to represent the cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorIcon {
+    /// The platform's standard arrow cursor.
+    Default,
+    /// An I-beam, indicating text that can be selected or edited.
+    Text,
+    /// A hand, indicating a clickable element.
+    Hand,
+    /// Crosshairs, often used for precise selection.
+    Crosshair,
+    /// Indicates that the current action is not allowed.
+    NotAllowed,
+}
+
+impl Default for CursorIcon {
+    fn default() -> Self {
+        CursorIcon::Default
+    }
+}
+
+/// A single change to the cursor presentation, to be applied by the window backend.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CursorStateChange {
+    /// The icon the cursor should use.
+    pub icon: CursorIcon,
+    /// Whether the cursor should be hidden.
+    pub hidden: bool,
+    /// Whether the cursor should be grabbed (confined to the window and hidden from the OS
+    /// cursor, with motion reported as relative deltas rather than absolute positions).
+    pub grabbed: bool,
+}
+
+/// Tracks the cursor presentation conrod would like the backend to show: its icon, whether
+/// it is hidden, and whether it is grabbed/confined to the window. Modelled after a
+/// `MouseContext` that only changes when explicitly told to, rather than resetting every
+/// update cycle the way event-driven state does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MouseContext {
+    cursor_type: CursorIcon,
+    cursor_hidden: bool,
+    cursor_grabbed: bool,
+    dirty: bool,
+}
+
+impl MouseContext {
+    /// Returns a fresh `MouseContext` with the default cursor, visible and not grabbed.
+    pub fn new() -> MouseContext {
+        MouseContext {
+            cursor_type: CursorIcon::Default,
+            cursor_hidden: false,
+            cursor_grabbed: false,
+            dirty: false,
+        }
+    }
+
+    /// Sets the icon the cursor should use.
+    pub fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        self.cursor_type = icon;
+        self.dirty = true;
+    }
+
+    /// Sets whether the cursor should be hidden.
+    pub fn hide_cursor(&mut self, hidden: bool) {
+        self.cursor_hidden = hidden;
+        self.dirty = true;
+    }
+
+    /// Sets whether the cursor should be grabbed/confined to the window.
+    pub fn grab_cursor(&mut self, grabbed: bool) {
+        self.cursor_grabbed = grabbed;
+        self.dirty = true;
+    }
+
+    /// Returns the most recently requested cursor icon.
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.cursor_type
+    }
+
+    /// Returns whether the cursor is currently requested to be hidden.
+    pub fn is_cursor_hidden(&self) -> bool {
+        self.cursor_hidden
+    }
+
+    /// Returns whether the cursor is currently requested to be grabbed.
+    pub fn is_cursor_grabbed(&self) -> bool {
+        self.cursor_grabbed
+    }
+
+    /// Drains the pending cursor-state change, if anything has changed since the last time
+    /// this was called. Returns `None` when nothing is pending, so the backend only has to
+    /// act when there is actually a change to apply.
+    pub fn take_change(&mut self) -> Option<CursorStateChange> {
+        if self.dirty {
+            self.dirty = false;
+            Some(CursorStateChange {
+                icon: self.cursor_type,
+                hidden: self.cursor_hidden,
+                grabbed: self.cursor_grabbed,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_change_drains_once_then_returns_none_until_changed_again() {
+        let mut cursor = MouseContext::new();
+        cursor.set_cursor_icon(CursorIcon::Hand);
+
+        assert_eq!(cursor.take_change(), Some(CursorStateChange {
+            icon: CursorIcon::Hand,
+            hidden: false,
+            grabbed: false,
+        }));
+        assert_eq!(cursor.take_change(), None);
+
+        cursor.grab_cursor(true);
+
+        assert_eq!(cursor.take_change(), Some(CursorStateChange {
+            icon: CursorIcon::Hand,
+            hidden: false,
+            grabbed: true,
+        }));
+        assert_eq!(cursor.take_change(), None);
+    }
+}
@@ -3,10 +3,47 @@
 //! and interpreting raw input events into high-level semantic events.
 
 use events::{InputState, UiEvent, MouseClick, MouseDrag, Scroll, InputProvider};
-use input::MouseButton;
+use events::{CursorIcon, CursorStateChange, MouseContext};
+use input::{MouseButton, Touch, TouchState};
 use position::{Point, Scalar};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use widget::Index;
 
+/// The default maximum gap between two clicks for the second to count towards a multi-click
+/// (e.g. the second click of a double-click).
+const DEFAULT_MULTI_CLICK_INTERVAL_MS: u64 = 500;
+
+/// The minimum speed, in points per second, a touch must be moving at when lifted for it to
+/// be interpreted as a flick/scroll rather than a drag release.
+const FLICK_VELOCITY_THRESHOLD: Scalar = 800.0;
+
+/// A touch trace that hasn't seen a `Move`/`Start` in longer than this is considered
+/// abandoned (e.g. its `End`/`Cancel` was lost, or the digitizer disconnected) and is evicted
+/// on `reset()` rather than kept around forever.
+const TOUCH_STALE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The `MouseButton` used to represent touch-originated clicks and drags, so that they can be
+/// reported through the same `MouseClick`/`MouseDrag` vocabulary widgets already understand.
+const TOUCH_BUTTON: MouseButton = MouseButton::Left;
+
+/// Records the most recent click so that subsequent clicks can be recognised as part of the
+/// same double/triple/etc-click sequence.
+struct LastClick {
+    button: MouseButton,
+    location: Point,
+    time: Instant,
+    count: u32,
+}
+
+/// Tracks a single active touch point from the moment it starts until it is lifted or
+/// cancelled, so that conrod can interpret its motion the same way it interprets a mouse drag.
+struct TouchTrace {
+    start_position: Point,
+    last_position: Point,
+    last_time: Instant,
+}
+
 /// Global input event handler that also implements `InputProvider`. The `Ui` passes all events
 /// to it's `GlobalInput` instance, which aggregates and interprets the events to provide
 /// so-called 'high-level' events to widgets. This input gets reset after every update by the `Ui`.
@@ -18,6 +55,19 @@ pub struct GlobalInput {
     pub current_state: InputState,
     events: Vec<UiEvent>,
     drag_threshold: Scalar,
+    /// The maximum gap in time between two clicks for them to be considered part of the same
+    /// multi-click sequence. The distance limit is `drag_threshold`.
+    multi_click_interval: Duration,
+    last_click: Option<LastClick>,
+    /// The sum of all relative mouse motion received so far this update cycle.
+    mouse_delta: Point,
+    /// The sum of all scroll motion received so far this update cycle.
+    scroll_delta: Point,
+    /// The currently active touches, keyed by their touch id. Kept separate from
+    /// `mouse_buttons` so that touch and mouse input can be handled simultaneously.
+    touches: HashMap<i64, TouchTrace>,
+    /// The cursor presentation (icon, visibility, grab) conrod would like the backend to show.
+    cursor: MouseContext,
 }
 
 /// Iterator over global `UiEvent`s. Unlike the `WidgetInputEventIterator`, this will
@@ -41,8 +91,17 @@ impl<'a> InputProvider<'a> for GlobalInput {
              self.mouse_position()
          })
     }
+
+    fn mouse_delta(&self) -> Point {
+        self.mouse_delta
+    }
+
+    fn scroll_delta(&self) -> Point {
+        self.scroll_delta
+    }
 }
 
+
 impl GlobalInput {
 
     /// Returns a fresh new `GlobalInput`
@@ -50,28 +109,115 @@ impl GlobalInput {
         GlobalInput{
             events: Vec::new(),
             drag_threshold: drag_threshold,
+            multi_click_interval: Duration::from_millis(DEFAULT_MULTI_CLICK_INTERVAL_MS),
+            last_click: None,
+            mouse_delta: [0.0, 0.0],
+            scroll_delta: [0.0, 0.0],
+            touches: HashMap::new(),
+            cursor: MouseContext::new(),
             start_state: InputState::new(),
             current_state: InputState::new(),
         }
     }
 
+    /// Sets the maximum gap in time between two clicks for the second to count as part of a
+    /// multi-click (e.g. a double-click) sequence rather than starting a new one.
+    pub fn set_multi_click_interval(&mut self, interval: Duration) {
+        self.multi_click_interval = interval;
+    }
+
+    /// Sets the icon the cursor should use.
+    pub fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        self.cursor.set_cursor_icon(icon);
+    }
+
+    /// Sets whether the cursor should be hidden.
+    pub fn hide_cursor(&mut self, hidden: bool) {
+        self.cursor.hide_cursor(hidden);
+    }
+
+    /// Sets whether the cursor should be grabbed/confined to the window. While grabbed, the
+    /// absolute mouse position stops being updated (the backend usually stops reporting it),
+    /// so `mouse_position` is left as-is and callers should track motion via `mouse_delta`
+    /// instead. For the same reason, `MouseClick`/`MouseDrag` classification (which compares
+    /// against absolute positions) is suppressed entirely while grabbed.
+    pub fn grab_cursor(&mut self, grabbed: bool) {
+        self.cursor.grab_cursor(grabbed);
+    }
+
+    /// Returns whether the cursor is currently requested to be grabbed.
+    pub fn cursor_grabbed(&self) -> bool {
+        self.cursor.is_cursor_grabbed()
+    }
+
+    /// Returns the most recently requested cursor icon.
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.cursor.cursor_icon()
+    }
+
+    /// Returns whether the cursor is currently requested to be hidden.
+    pub fn is_cursor_hidden(&self) -> bool {
+        self.cursor.is_cursor_hidden()
+    }
+
+    /// Drains the pending cursor-state change, if the icon, visibility, or grab state has
+    /// changed since the last time this was called. Intended to be polled by the `Ui`'s
+    /// window backend once per update cycle.
+    pub fn drain_cursor_changes(&mut self) -> Option<CursorStateChange> {
+        self.cursor.take_change()
+    }
+
     /// Adds a new event and updates the internal state.
     pub fn push_event(&mut self, event: UiEvent) {
-        use input::Input::{Release, Move};
-        use input::Motion::MouseRelative;
-        use input::Motion::MouseScroll;
+        use input::Input::{Release, Move, Touch as TouchInput};
+        use input::Motion::{MouseCursor, MouseRelative, MouseScroll};
         use input::Button::Mouse;
 
-        let maybe_new_event = match event {
-            UiEvent::Raw(Release(Mouse(button))) => self.handle_mouse_release(button),
-            UiEvent::Raw(Move(MouseRelative(x, y))) => self.handle_mouse_move([x, y]),
-            UiEvent::Raw(Move(MouseScroll(x, y))) => self.mouse_scroll(x, y),
-            _ => None
+        // While the cursor is grabbed, the backend's absolute mouse position typically stops
+        // updating (it's pinned at the centre of the window), so ignore it and rely solely on
+        // the accumulated `mouse_delta` from `MouseRelative` events instead.
+        let ignore_absolute_position = self.cursor.is_cursor_grabbed()
+            && match event {
+                UiEvent::Raw(Move(MouseCursor(_, _))) => true,
+                _ => false,
+            };
+
+        // While the cursor is grabbed, `current_state.mouse_position` is frozen (see above), so
+        // comparing it against a press origin would either never register as a drag or, worse,
+        // always register as one depending on where the freeze happened to catch it. Absolute-
+        // position click/drag classification doesn't apply under grab; callers track motion via
+        // `mouse_delta` directly instead, per `grab_cursor`'s doc comment.
+        let grabbed = self.cursor.is_cursor_grabbed();
+
+        let new_events = match event {
+            UiEvent::Raw(Release(Mouse(button))) => {
+                if grabbed {
+                    Vec::new()
+                } else {
+                    self.handle_mouse_release(button, Instant::now()).into_iter().collect()
+                }
+            },
+            UiEvent::Raw(Move(MouseRelative(x, y))) => {
+                self.mouse_delta = [self.mouse_delta[0] + x, self.mouse_delta[1] + y];
+                if grabbed {
+                    Vec::new()
+                } else {
+                    self.handle_mouse_move(self.current_state.mouse_position)
+                }
+            },
+            UiEvent::Raw(Move(MouseScroll(x, y))) => {
+                self.scroll_delta = [self.scroll_delta[0] + x, self.scroll_delta[1] + y];
+                self.mouse_scroll(x, y).into_iter().collect()
+            },
+            UiEvent::Raw(TouchInput(touch)) => self.handle_touch(touch, Instant::now()),
+            _ => Vec::new(),
         };
 
-        self.current_state.update(&event);
+        if !ignore_absolute_position {
+            self.current_state.update(&event);
+        }
         self.events.push(event);
-        if let Some(new_event) = maybe_new_event {
+        for new_event in new_events {
             self.push_event(new_event);
         }
     }
@@ -81,6 +227,13 @@ impl GlobalInput {
     pub fn reset(&mut self) {
         self.events.clear();
         self.start_state = self.current_state.clone();
+        self.mouse_delta = [0.0, 0.0];
+        self.scroll_delta = [0.0, 0.0];
+
+        // Touches are otherwise only removed on `End`/`Cancel`, so a lost event would leave
+        // the trace behind forever; evict anything that's gone quiet for too long.
+        let now = Instant::now();
+        self.touches.retain(|_, trace| now.duration_since(trace.last_time) < TOUCH_STALE_TIMEOUT);
     }
 
     /// Returns the most up to date position of the mouse
@@ -112,12 +265,15 @@ impl GlobalInput {
         }))
     }
 
-    fn handle_mouse_move(&self, move_to: Point) -> Option<UiEvent> {
-        self.current_state.mouse_buttons.pressed_button().and_then(|btn_and_point| {
-            if self.is_drag(btn_and_point.1, move_to) {
+    /// Produces a `MouseDrag` for every currently held mouse button whose movement from its
+    /// own press origin exceeds `drag_threshold`, so that chorded drags (e.g. left+right held
+    /// together) are all reported rather than only the first-pressed button.
+    fn handle_mouse_move(&self, move_to: Point) -> Vec<UiEvent> {
+        self.current_state.mouse_buttons.iter().filter_map(|(button, start)| {
+            if self.is_drag(start, move_to) {
                 Some(UiEvent::MouseDrag(MouseDrag{
-                    button: btn_and_point.0,
-                    start: btn_and_point.1,
+                    button: button,
+                    start: start,
                     end: move_to,
                     in_progress: true,
                     modifier: self.current_state.modifiers
@@ -125,29 +281,133 @@ impl GlobalInput {
             } else {
                 None
             }
-        })
+        }).collect()
+    }
+
+    /// Translates a single touch point's lifecycle into the same `MouseDrag`/`MouseClick`
+    /// vocabulary produced for mouse input, so widgets need not special-case touch input.
+    fn handle_touch(&mut self, touch: Touch, time: Instant) -> Vec<UiEvent> {
+        let modifiers = self.current_state.modifiers;
+        match touch.state {
+            TouchState::Start => {
+                self.touches.insert(touch.id, TouchTrace {
+                    start_position: touch.xy,
+                    last_position: touch.xy,
+                    last_time: time,
+                });
+                Vec::new()
+            },
+            TouchState::Move => {
+                let drag_threshold = self.drag_threshold;
+                if let Some(trace) = self.touches.get_mut(&touch.id) {
+                    let is_drag = distance_between(trace.start_position, touch.xy) > drag_threshold;
+                    trace.last_position = touch.xy;
+                    trace.last_time = time;
+                    if is_drag {
+                        return vec![UiEvent::MouseDrag(MouseDrag {
+                            button: TOUCH_BUTTON,
+                            start: trace.start_position,
+                            end: touch.xy,
+                            in_progress: true,
+                            modifier: modifiers,
+                        })];
+                    }
+                }
+                Vec::new()
+            },
+            // A cancelled touch was never lifted, so it is not a tap, drag release, or flick;
+            // just drop the trace and emit nothing.
+            TouchState::Cancel => {
+                self.touches.remove(&touch.id);
+                Vec::new()
+            },
+            TouchState::End => {
+                match self.touches.remove(&touch.id) {
+                    Some(trace) => {
+                        if self.is_drag(trace.start_position, touch.xy) {
+                            // Use the final segment's motion/time, not the whole gesture's
+                            // average, so a slow drag that ends in a quick flick is detected
+                            // and the reported `Scroll` delta matches the velocity test.
+                            let elapsed = time.duration_since(trace.last_time);
+                            let seconds = elapsed.as_secs() as Scalar
+                                + (elapsed.subsec_nanos() as Scalar / 1_000_000_000.0);
+                            let dx = touch.xy[0] - trace.last_position[0];
+                            let dy = touch.xy[1] - trace.last_position[1];
+                            let velocity = distance_between(trace.last_position, touch.xy)
+                                / seconds.max(0.001);
+                            if velocity >= FLICK_VELOCITY_THRESHOLD {
+                                vec![UiEvent::Scroll(Scroll {
+                                    x: dx,
+                                    y: dy,
+                                    modifiers: modifiers,
+                                })]
+                            } else {
+                                vec![UiEvent::MouseDrag(MouseDrag {
+                                    button: TOUCH_BUTTON,
+                                    start: trace.start_position,
+                                    end: touch.xy,
+                                    in_progress: false,
+                                    modifier: modifiers,
+                                })]
+                            }
+                        } else {
+                            vec![UiEvent::MouseClick(MouseClick {
+                                button: TOUCH_BUTTON,
+                                location: touch.xy,
+                                modifier: modifiers,
+                                count: 1,
+                            })]
+                        }
+                    },
+                    None => Vec::new(),
+                }
+            },
+        }
     }
 
-    fn handle_mouse_release(&self, button: MouseButton) -> Option<UiEvent> {
+    fn handle_mouse_release(&mut self, button: MouseButton, time: Instant) -> Option<UiEvent> {
+        let mouse_position = self.current_state.mouse_position;
+        let modifiers = self.current_state.modifiers;
         self.current_state.mouse_buttons.get(button).map(|point| {
-            if self.is_drag(point, self.current_state.mouse_position) {
+            if self.is_drag(point, mouse_position) {
+                self.last_click = None;
                 UiEvent::MouseDrag(MouseDrag{
                     button: button,
                     start: point,
-                    end: self.current_state.mouse_position,
-                    modifier: self.current_state.modifiers,
+                    end: mouse_position,
+                    modifier: modifiers,
                     in_progress: false
                 })
             } else {
+                let count = self.click_count(button, point, time);
+                self.last_click = Some(LastClick {
+                    button: button,
+                    location: point,
+                    time: time,
+                    count: count,
+                });
                 UiEvent::MouseClick(MouseClick {
                     button: button,
                     location: point,
-                    modifier: self.current_state.modifiers
+                    modifier: modifiers,
+                    count: count,
                 })
             }
         })
     }
 
+    /// Determines the click-count of a click at `point` with `button`, given the previously
+    /// recorded click (if any). Clicks that land outside the multi-click interval or move
+    /// further than `drag_threshold` from the previous click always restart the sequence.
+    fn click_count(&self, button: MouseButton, point: Point, time: Instant) -> u32 {
+        match self.last_click {
+            Some(ref last) if last.button == button
+                && time.duration_since(last.time) <= self.multi_click_interval
+                && !self.is_drag(last.location, point) => last.count + 1,
+            _ => 1,
+        }
+    }
+
     fn is_drag(&self, a: Point, b: Point) -> bool {
         distance_between(a, b) > self.drag_threshold
     }
@@ -157,4 +417,190 @@ fn distance_between(a: Point, b: Point) -> Scalar {
     let dx_2 = (a[0] - b[0]).powi(2);
     let dy_2 = (a[1] - b[1]).powi(2);
     (dx_2 + dy_2).abs().sqrt()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input_with_last_click(button: MouseButton, location: Point, time: Instant, count: u32)
+        -> GlobalInput
+    {
+        let mut input = GlobalInput::new(4.0);
+        input.last_click = Some(LastClick {
+            button: button,
+            location: location,
+            time: time,
+            count: count,
+        });
+        input
+    }
+
+    #[test]
+    fn click_count_increments_within_interval_and_distance() {
+        let base = Instant::now();
+        let input = input_with_last_click(MouseButton::Left, [0.0, 0.0], base, 1);
+        let count = input.click_count(MouseButton::Left, [1.0, 1.0], base + Duration::from_millis(100));
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn click_count_still_increments_at_exactly_the_interval_boundary() {
+        let base = Instant::now();
+        let input = input_with_last_click(MouseButton::Left, [0.0, 0.0], base, 1);
+        let count = input.click_count(
+            MouseButton::Left, [0.0, 0.0], base + Duration::from_millis(DEFAULT_MULTI_CLICK_INTERVAL_MS));
+        assert_eq!(count, 2, "a click landing exactly on the interval boundary should still count");
+    }
+
+    #[test]
+    fn click_count_resets_once_past_the_interval() {
+        let base = Instant::now();
+        let input = input_with_last_click(MouseButton::Left, [0.0, 0.0], base, 2);
+        let count = input.click_count(
+            MouseButton::Left, [0.0, 0.0], base + Duration::from_millis(DEFAULT_MULTI_CLICK_INTERVAL_MS + 1));
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn click_count_resets_beyond_drag_threshold() {
+        let base = Instant::now();
+        let input = input_with_last_click(MouseButton::Left, [0.0, 0.0], base, 2);
+        let count = input.click_count(MouseButton::Left, [100.0, 100.0], base + Duration::from_millis(50));
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn click_count_resets_for_a_different_button() {
+        let base = Instant::now();
+        let input = input_with_last_click(MouseButton::Left, [0.0, 0.0], base, 3);
+        let count = input.click_count(MouseButton::Right, [0.0, 0.0], base + Duration::from_millis(50));
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn handle_mouse_move_emits_a_drag_per_held_button() {
+        let mut input = GlobalInput::new(4.0);
+        input.current_state.mouse_buttons.set(MouseButton::Left, [0.0, 0.0]);
+        input.current_state.mouse_buttons.set(MouseButton::Right, [100.0, 100.0]);
+
+        let events = input.handle_mouse_move([10.0, 0.0]);
+
+        assert_eq!(events.len(), 2);
+        assert!(events.contains(&UiEvent::MouseDrag(MouseDrag {
+            button: MouseButton::Left,
+            start: [0.0, 0.0],
+            end: [10.0, 0.0],
+            in_progress: true,
+            modifier: input.current_state.modifiers,
+        })));
+        assert!(events.contains(&UiEvent::MouseDrag(MouseDrag {
+            button: MouseButton::Right,
+            start: [100.0, 100.0],
+            end: [10.0, 0.0],
+            in_progress: true,
+            modifier: input.current_state.modifiers,
+        })));
+    }
+
+    fn touch(id: i64, xy: Point, state: TouchState) -> Touch {
+        Touch { id: id, xy: xy, state: state }
+    }
+
+    #[test]
+    fn touch_end_reports_a_flick_when_the_final_segment_is_fast() {
+        let mut input = GlobalInput::new(4.0);
+        let start = Instant::now();
+        input.handle_touch(touch(1, [0.0, 0.0], TouchState::Start), start);
+
+        // Moves fast enough, right before lifting, that the velocity clears
+        // `FLICK_VELOCITY_THRESHOLD`.
+        let events = input.handle_touch(
+            touch(1, [100.0, 0.0], TouchState::End), start + Duration::from_millis(10));
+
+        assert_eq!(events, vec![UiEvent::Scroll(Scroll {
+            x: 100.0,
+            y: 0.0,
+            modifiers: input.current_state.modifiers,
+        })]);
+    }
+
+    #[test]
+    fn touch_end_reports_a_tap_when_lifted_within_the_drag_threshold() {
+        let mut input = GlobalInput::new(4.0);
+        let start = Instant::now();
+        input.handle_touch(touch(1, [0.0, 0.0], TouchState::Start), start);
+
+        // Lifted close to where it started, well within `drag_threshold`, so this is a tap.
+        let events = input.handle_touch(
+            touch(1, [1.0, 0.0], TouchState::End), start + Duration::from_millis(50));
+
+        assert_eq!(events, vec![UiEvent::MouseClick(MouseClick {
+            button: TOUCH_BUTTON,
+            location: [1.0, 0.0],
+            modifier: input.current_state.modifiers,
+            count: 1,
+        })]);
+    }
+
+    #[test]
+    fn grabbed_cursor_suppresses_click_and_drag_but_mouse_delta_still_accumulates() {
+        use input::Input::{Press, Release, Move};
+        use input::Motion::MouseRelative;
+        use input::Button::Mouse;
+
+        let mut input = GlobalInput::new(4.0);
+        input.grab_cursor(true);
+
+        input.push_event(UiEvent::Raw(Press(Mouse(MouseButton::Left))));
+        input.push_event(UiEvent::Raw(Move(MouseRelative(50.0, 0.0))));
+        input.push_event(UiEvent::Raw(Release(Mouse(MouseButton::Left))));
+
+        let clicks_and_drags = input.all_events().filter(|event| match **event {
+            UiEvent::MouseClick(_) | UiEvent::MouseDrag(_) => true,
+            _ => false,
+        }).count();
+        assert_eq!(clicks_and_drags, 0, "grab should suppress MouseClick/MouseDrag classification");
+        assert_eq!(input.mouse_delta(), [50.0, 0.0]);
+    }
+
+    #[test]
+    fn mouse_delta_and_scroll_delta_accumulate_and_reset_clears_them() {
+        use input::Input::Move;
+        use input::Motion::{MouseRelative, MouseScroll};
+
+        let mut input = GlobalInput::new(4.0);
+        input.push_event(UiEvent::Raw(Move(MouseRelative(3.0, 4.0))));
+        input.push_event(UiEvent::Raw(Move(MouseRelative(1.0, -2.0))));
+        input.push_event(UiEvent::Raw(Move(MouseScroll(2.0, 0.5))));
+        input.push_event(UiEvent::Raw(Move(MouseScroll(-1.0, 1.5))));
+
+        assert_eq!(input.mouse_delta(), [4.0, 2.0]);
+        assert_eq!(input.scroll_delta(), [1.0, 2.0]);
+
+        input.reset();
+
+        assert_eq!(input.mouse_delta(), [0.0, 0.0]);
+        assert_eq!(input.scroll_delta(), [0.0, 0.0]);
+    }
+
+    #[test]
+    fn touch_end_reports_a_drag_release_when_the_final_segment_is_slow() {
+        let mut input = GlobalInput::new(4.0);
+        let start = Instant::now();
+        input.handle_touch(touch(1, [0.0, 0.0], TouchState::Start), start);
+
+        // Moved past `drag_threshold` overall, but slowly, so this is a drag release, not a
+        // flick.
+        let events = input.handle_touch(
+            touch(1, [50.0, 0.0], TouchState::End), start + Duration::from_secs(1));
+
+        assert_eq!(events, vec![UiEvent::MouseDrag(MouseDrag {
+            button: TOUCH_BUTTON,
+            start: [0.0, 0.0],
+            end: [50.0, 0.0],
+            in_progress: false,
+            modifier: input.current_state.modifiers,
+        })]);
+    }
+}
@@ -0,0 +1,36 @@
+//! Defines the `InputProvider` trait, the common interface widgets use to query input.
+
+use input::MouseButton;
+use position::Point;
+
+use super::input_state::MouseButtonMapIter;
+use super::{InputState, UiEvent};
+
+/// A type that can provide access to the `UiEvent`s and `InputState` relevant to it.
+///
+/// `GlobalInput` implements this trait, exposing all events.
+pub trait InputProvider<'a> {
+    /// The iterator type returned by `all_events`.
+    type Events: Iterator<Item=&'a UiEvent>;
+
+    /// Returns an iterator over all of the events available to this provider.
+    fn all_events(&'a self) -> Self::Events;
+
+    /// Returns the most up to date `InputState`.
+    fn current_state(&'a self) -> &'a InputState;
+
+    /// If the given button is currently pressed, returns the current position of the mouse.
+    fn mouse_button_down(&self, button: MouseButton) -> Option<Point>;
+
+    /// Returns the sum of all relative mouse motion accumulated so far this update cycle.
+    fn mouse_delta(&self) -> Point;
+
+    /// Returns the sum of all scroll motion accumulated so far this update cycle.
+    fn scroll_delta(&self) -> Point;
+
+    /// Returns an iterator over every currently held mouse button, along with the point at
+    /// which it was originally pressed, so that chorded combinations can be inspected.
+    fn mouse_buttons_down(&'a self) -> MouseButtonMapIter<'a> {
+        self.current_state().mouse_buttons.iter()
+    }
+}
@@ -0,0 +1,105 @@
+//! Defines `InputState`, a snapshot of conrod's understanding of the current input devices.
+
+use std::collections::HashMap;
+
+use input::MouseButton;
+use input::keyboard::{ModifierKey, NO_MODIFIER};
+use position::Point;
+use widget::Index;
+
+use super::UiEvent;
+
+/// A snapshot of the current state of the input devices, built up by applying a stream of
+/// `UiEvent`s.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputState {
+    /// The most recent position of the mouse.
+    pub mouse_position: Point,
+    /// The set of mouse buttons that are currently pressed, along with the point at which
+    /// each one was originally pressed.
+    pub mouse_buttons: MouseButtonMap,
+    /// The modifier keys that are currently held down.
+    pub modifiers: ModifierKey,
+    /// The index of the widget that is currently capturing the mouse, if any.
+    pub widget_capturing_mouse: Option<Index>,
+    /// The index of the widget that is currently capturing the keyboard, if any.
+    pub widget_capturing_keyboard: Option<Index>,
+}
+
+impl InputState {
+    /// Construct a fresh `InputState` with no buttons pressed and the mouse at the origin.
+    pub fn new() -> InputState {
+        InputState {
+            mouse_position: [0.0, 0.0],
+            mouse_buttons: MouseButtonMap::new(),
+            modifiers: NO_MODIFIER,
+            widget_capturing_mouse: None,
+            widget_capturing_keyboard: None,
+        }
+    }
+
+    /// Updates the state to reflect the given event having occurred.
+    pub fn update(&mut self, event: &UiEvent) {
+        use input::Input::{Press, Release, Move};
+        use input::Motion::MouseCursor;
+        use input::Button::Mouse;
+
+        if let UiEvent::Raw(raw) = *event {
+            match raw {
+                Press(Mouse(button)) => self.mouse_buttons.set(button, self.mouse_position),
+                Release(Mouse(button)) => self.mouse_buttons.unset(button),
+                Move(MouseCursor(x, y)) => self.mouse_position = [x, y],
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Tracks the set of currently pressed mouse buttons, along with the point at which each one
+/// was originally pressed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MouseButtonMap {
+    buttons: HashMap<MouseButton, Point>,
+}
+
+impl MouseButtonMap {
+    /// Returns a new, empty `MouseButtonMap`.
+    pub fn new() -> MouseButtonMap {
+        MouseButtonMap { buttons: HashMap::new() }
+    }
+
+    /// Returns the point at which the given button was pressed, if it is currently down.
+    pub fn get(&self, button: MouseButton) -> Option<Point> {
+        self.buttons.get(&button).cloned()
+    }
+
+    /// Records that the given button has been pressed at the given point.
+    pub fn set(&mut self, button: MouseButton, point: Point) {
+        self.buttons.insert(button, point);
+    }
+
+    /// Records that the given button has been released.
+    pub fn unset(&mut self, button: MouseButton) {
+        self.buttons.remove(&button);
+    }
+
+    /// Returns an iterator over every currently pressed button, along with the point at
+    /// which each one was pressed.
+    pub fn iter(&self) -> MouseButtonMapIter {
+        MouseButtonMapIter { inner: self.buttons.iter() }
+    }
+}
+
+/// Iterator over the currently pressed mouse buttons and the points at which they were
+/// pressed. Produced by `MouseButtonMap::iter`.
+pub struct MouseButtonMapIter<'a> {
+    inner: ::std::collections::hash_map::Iter<'a, MouseButton, Point>,
+}
+
+impl<'a> Iterator for MouseButtonMapIter<'a> {
+    type Item = (MouseButton, Point);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(&button, &point)| (button, point))
+    }
+}
@@ -0,0 +1,17 @@
+//! Types and traits for working with conrod's high-level, semantic input events.
+//!
+//! Raw events from the window backend are fed into a `GlobalInput`, which aggregates and
+//! interprets them into the `UiEvent`s described in this module. Widgets then consume these
+//! through the `InputProvider` trait rather than working with raw window events directly.
+
+pub use self::cursor::{CursorIcon, CursorStateChange, MouseContext};
+pub use self::global_input::GlobalInput;
+pub use self::input_provider::InputProvider;
+pub use self::input_state::InputState;
+pub use self::ui_event::{UiEvent, MouseClick, MouseDrag, Scroll};
+
+mod cursor;
+mod global_input;
+mod input_provider;
+mod input_state;
+mod ui_event;
@@ -0,0 +1,64 @@
+//! Defines the `UiEvent` type along with the high-level event payloads it carries.
+
+use input::{Input, MouseButton};
+use input::keyboard::ModifierKey;
+use position::Point;
+
+/// A high-level event interpreted from one or more raw `Input` events.
+///
+/// `GlobalInput` produces these by aggregating raw events, so that widgets can work with
+/// semantic events like "a click happened here" rather than replaying raw button state.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UiEvent {
+    /// A raw, unmodified event as produced by the window backend.
+    Raw(Input),
+    /// A mouse button was pressed and released without enough movement to count as a drag.
+    MouseClick(MouseClick),
+    /// A mouse button was pressed and moved beyond the `drag_threshold` before being released.
+    MouseDrag(MouseDrag),
+    /// The mouse wheel (or equivalent) was scrolled.
+    Scroll(Scroll),
+}
+
+/// Information about a single mouse click, including how many clicks were made in quick
+/// succession (analogous to `MouseEvent.detail` in the DOM).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MouseClick {
+    /// The button that was clicked.
+    pub button: MouseButton,
+    /// The location at which the click occurred.
+    pub location: Point,
+    /// The modifier keys that were held down at the time of the click.
+    pub modifier: ModifierKey,
+    /// The number of consecutive clicks this click is part of (1 for a single click, 2 for a
+    /// double-click, 3 for a triple-click, and so on).
+    pub count: u32,
+}
+
+/// Information about a mouse drag, from the point the button was first pressed to its
+/// current (or final) position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MouseDrag {
+    /// The button that is being (or was) held down for the drag.
+    pub button: MouseButton,
+    /// The position at which the button was originally pressed.
+    pub start: Point,
+    /// The most recent (or final) position of the mouse.
+    pub end: Point,
+    /// The modifier keys that were held down during the drag.
+    pub modifier: ModifierKey,
+    /// Whether the drag is still ongoing (`true`) or has finished (`false`, i.e. the button
+    /// has been released).
+    pub in_progress: bool,
+}
+
+/// A single scroll event, combining the horizontal and vertical scroll amounts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scroll {
+    /// The horizontal scroll amount.
+    pub x: f64,
+    /// The vertical scroll amount.
+    pub y: f64,
+    /// The modifier keys that were held down at the time of the scroll.
+    pub modifiers: ModifierKey,
+}